@@ -0,0 +1,36 @@
+//! Text-object selection and navigation.
+
+use serde::{Deserialize, Serialize};
+
+/// How a [`Request::TextObjects`] request should transform the current selections, given the
+/// node(s) captured by `pattern`.
+///
+/// [`Request::TextObjects`]: crate::server::request::Request::TextObjects
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationMode {
+  /// Select the whole captured node.
+  SelectWhole,
+  /// Select only the node's "inner" range (e.g. a block's content, without its delimiters).
+  SelectInner,
+  /// Move the selection to the next match of `pattern`.
+  Next,
+  /// Move the selection to the previous match of `pattern`.
+  Previous,
+}
+
+/// Which element-level operation a list-element text-objects request performs, acting on the
+/// `entry` / `element` captures `tree_sitter::elements` resolves rather than on the whole node
+/// `pattern` captures.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ElementOp {
+  /// Select the element the primary selection is on (or the next one after it).
+  Select,
+  /// Move the selection to the next element.
+  Next,
+  /// Move the selection to the previous element.
+  Previous,
+  /// Expand the selection to every element, e.g. to select all call arguments at once.
+  ExpandAll,
+}
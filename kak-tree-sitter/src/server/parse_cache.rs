@@ -0,0 +1,210 @@
+//! Per-buffer parse cache, for incremental reparsing.
+//!
+//! [`Request::Highlight`]: crate::server::request::Request::Highlight
+
+use std::collections::HashMap;
+
+use tree_sitter::{InputEdit, Point, Tree};
+
+/// What we remember about a buffer between two highlight requests.
+#[derive(Debug)]
+struct CachedParse {
+  lang: String,
+  timestamp: u64,
+  source: String,
+  tree: Tree,
+}
+
+/// Caches the last parsed [`Tree`] of every known buffer, keyed by buffer path.
+#[derive(Debug, Default)]
+pub struct ParseCache {
+  buffers: HashMap<String, CachedParse>,
+}
+
+impl ParseCache {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Drop the cached tree for `buffer`, forcing the next highlight request to reparse it fully.
+  pub fn invalidate(&mut self, buffer: &str) {
+    self.buffers.remove(buffer);
+  }
+
+  /// Edits to replay on the previous [`Tree`] of `buffer`, if any. `None` (reparse from scratch)
+  /// if there is no cached entry, `lang` differs, or `timestamp` isn't newer than cached.
+  pub fn edits_for(&self, buffer: &str, lang: &str, timestamp: u64, source: &str) -> Option<(&Tree, Vec<InputEdit>)> {
+    let cached = self.buffers.get(buffer)?;
+
+    if cached.lang != lang || timestamp <= cached.timestamp {
+      return None;
+    }
+
+    let edits = diff_edits(&cached.source, source);
+
+    Some((&cached.tree, edits))
+  }
+
+  /// Remember `tree` as the latest parse of `buffer`, superseding whatever was cached before.
+  pub fn update(&mut self, buffer: impl Into<String>, lang: impl Into<String>, timestamp: u64, source: impl Into<String>, tree: Tree) {
+    self.buffers.insert(
+      buffer.into(),
+      CachedParse {
+        lang: lang.into(),
+        timestamp,
+        source: source.into(),
+        tree,
+      },
+    );
+  }
+}
+
+/// Turn the byte range that differs between `old` and `new` into an [`InputEdit`], matched by
+/// `char` so a changed multi-byte character can never split mid-character.
+fn diff_edits(old: &str, new: &str) -> Vec<InputEdit> {
+  let start_byte = common_prefix_len(old, new);
+  let suffix_len = common_suffix_len(&old[start_byte..], &new[start_byte..]);
+
+  let old_end_byte = old.len() - suffix_len;
+  let new_end_byte = new.len() - suffix_len;
+
+  if start_byte == old_end_byte && start_byte == new_end_byte {
+    // identical buffers; nothing changed
+    return Vec::new();
+  }
+
+  vec![InputEdit {
+    start_byte,
+    old_end_byte,
+    new_end_byte,
+    start_position: point_at(old, start_byte),
+    old_end_position: point_at(old, old_end_byte),
+    new_end_position: point_at(new, new_end_byte),
+  }]
+}
+
+/// Byte length of the longest common prefix of `old` and `new`, landing on a char boundary.
+fn common_prefix_len(old: &str, new: &str) -> usize {
+  old
+    .char_indices()
+    .zip(new.chars())
+    .take_while(|((_, a), b)| a == b)
+    .map(|((idx, c), _)| idx + c.len_utf8())
+    .last()
+    .unwrap_or(0)
+}
+
+/// Byte length of the longest common suffix of `old` and `new`, landing on a char boundary.
+fn common_suffix_len(old: &str, new: &str) -> usize {
+  old
+    .chars()
+    .rev()
+    .zip(new.chars().rev())
+    .take_while(|(a, b)| a == b)
+    .map(|(c, _)| c.len_utf8())
+    .sum()
+}
+
+/// Translate a byte offset into a tree-sitter [`Point`] (0-indexed row/column, in bytes).
+fn point_at(source: &str, byte: usize) -> Point {
+  let mut row = 0;
+  let mut col = 0;
+
+  for (idx, c) in source.char_indices() {
+    if idx >= byte {
+      break;
+    }
+
+    if c == '\n' {
+      row += 1;
+      col = 0;
+    } else {
+      col += c.len_utf8();
+    }
+  }
+
+  Point { row, column: col }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{common_prefix_len, common_suffix_len, diff_edits, point_at};
+
+  #[test]
+  fn mid_buffer_single_char_edit() {
+    let old = "let x = 1;";
+    let new = "let x = 2;";
+
+    let edits = diff_edits(old, new);
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].start_byte, 8);
+    assert_eq!(edits[0].old_end_byte, 9);
+    assert_eq!(edits[0].new_end_byte, 9);
+  }
+
+  #[test]
+  fn insert_at_eof() {
+    let old = "let x = 1;";
+    let new = "let x = 1;\n";
+
+    let edits = diff_edits(old, new);
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].start_byte, 10);
+    assert_eq!(edits[0].old_end_byte, 10);
+    assert_eq!(edits[0].new_end_byte, 11);
+  }
+
+  #[test]
+  fn delete() {
+    let old = "let x = 1;";
+    let new = "let x = ;";
+
+    let edits = diff_edits(old, new);
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].start_byte, 8);
+    assert_eq!(edits[0].old_end_byte, 9);
+    assert_eq!(edits[0].new_end_byte, 8);
+  }
+
+  #[test]
+  fn identical_buffers_produce_no_edits() {
+    assert_eq!(diff_edits("same", "same"), Vec::new());
+  }
+
+  #[test]
+  fn utf8_edit_snaps_to_char_boundary() {
+    // `é` is a two-byte UTF-8 char; replacing it with `e` must not split it mid-byte.
+    let old = "café au lait";
+    let new = "cafe au lait";
+
+    let edits = diff_edits(old, new);
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].start_byte, 3);
+    assert_eq!(edits[0].old_end_byte, 5);
+    assert_eq!(edits[0].new_end_byte, 4);
+  }
+
+  #[test]
+  fn multi_line_edit_points() {
+    let old = "fn a() {}\nfn b() {}\n";
+    let new = "fn a() {}\nfn changed() {}\n";
+
+    let edits = diff_edits(old, new);
+    assert_eq!(edits.len(), 1);
+
+    let edit = &edits[0];
+    assert_eq!(edit.start_position, point_at(old, edit.start_byte));
+    assert_eq!(edit.old_end_position, point_at(old, edit.old_end_byte));
+    assert_eq!(edit.new_end_position, point_at(new, edit.new_end_byte));
+    assert_eq!(edit.start_position.row, 1);
+    assert_eq!(edit.start_position.column, 3);
+  }
+
+  #[test]
+  fn common_prefix_and_suffix_len() {
+    assert_eq!(common_prefix_len("abcdef", "abcxyz"), 3);
+    assert_eq!(common_suffix_len("abcdef", "xyzdef"), 3);
+    assert_eq!(common_prefix_len("", "abc"), 0);
+    assert_eq!(common_suffix_len("", "abc"), 0);
+  }
+}
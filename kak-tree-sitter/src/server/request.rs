@@ -4,7 +4,11 @@ use std::{fmt::Debug, io::Write, os::unix::net::UnixStream};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{error::OhNo, kakoune::text_objects::OperationMode, tree_sitter::nav};
+use crate::{
+  error::OhNo,
+  kakoune::text_objects::{ElementOp, OperationMode},
+  tree_sitter::nav,
+};
 
 use super::resources::ServerResources;
 
@@ -89,6 +93,10 @@ pub enum Request {
     pattern: String,
     selections: String,
     mode: OperationMode,
+
+    /// When set, act on `pattern`'s `entry` / `element` captures instead of the whole node.
+    #[serde(default)]
+    element: Option<ElementOp>,
   },
 
   /// Request to navigate the tree-sitter tree on selections.
@@ -99,6 +107,16 @@ pub enum Request {
     selections: String,
     dir: nav::Dir,
   },
+
+  /// Request for the sticky-context breadcrumb of the primary selection.
+  ///
+  /// Returns the chain of named nodes enclosing the primary selection, innermost to outermost.
+  Context {
+    client: String,
+    buffer: String,
+    lang: String,
+    selections: String,
+  },
 }
 
 impl Request {
@@ -108,6 +126,7 @@ impl Request {
       Request::Highlight { client, .. } => Some(client.as_str()),
       Request::TextObjects { client, .. } => Some(client.as_str()),
       Request::Nav { client, .. } => Some(client.as_str()),
+      Request::Context { client, .. } => Some(client.as_str()),
     }
   }
 }
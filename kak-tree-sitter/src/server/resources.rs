@@ -0,0 +1,35 @@
+//! Resources shared by everything the server does while it is running.
+
+use std::path::{Path, PathBuf};
+
+use super::parse_cache::ParseCache;
+
+/// State shared across requests for the lifetime of the server.
+#[derive(Debug)]
+pub struct ServerResources {
+  socket_path: PathBuf,
+
+  /// Per-buffer parse cache, shared by `Highlight`, `TextObjects` and `Nav`.
+  parse_cache: ParseCache,
+}
+
+impl ServerResources {
+  pub fn new(socket_path: impl Into<PathBuf>) -> Self {
+    Self {
+      socket_path: socket_path.into(),
+      parse_cache: ParseCache::new(),
+    }
+  }
+
+  pub fn socket_path(&self) -> &Path {
+    &self.socket_path
+  }
+
+  pub fn parse_cache(&self) -> &ParseCache {
+    &self.parse_cache
+  }
+
+  pub fn parse_cache_mut(&mut self) -> &mut ParseCache {
+    &mut self.parse_cache
+  }
+}
@@ -0,0 +1,109 @@
+//! Sticky-context breadcrumbs: the chain of declarations enclosing a selection.
+//!
+//! [`Request::Context`]: crate::server::request::Request::Context
+
+use tree_sitter::Node;
+
+/// One entry in a sticky-context breadcrumb.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ContextLine {
+  /// 1-indexed line the enclosing node starts on.
+  pub line: usize,
+  /// The node's first line of text, suitable for a breadcrumb or a pinned header.
+  pub text: String,
+}
+
+/// Walk up from `node` to the root, keeping only nodes whose kind appears in `context_kinds`
+/// (e.g. `function_item`, `impl_item`, `struct_item` for Rust), innermost node first.
+pub fn enclosing_context(node: Node, context_kinds: &[String], source: &str) -> Vec<ContextLine> {
+  let mut context = Vec::new();
+  let mut current = Some(node);
+
+  while let Some(n) = current {
+    if context_kinds.iter().any(|kind| kind == n.kind()) {
+      context.push(ContextLine {
+        line: n.start_position().row + 1,
+        text: first_line(source, n).to_owned(),
+      });
+    }
+
+    current = n.parent();
+  }
+
+  context
+}
+
+/// The node's own text, truncated to its first line.
+fn first_line<'a>(source: &'a str, node: Node) -> &'a str {
+  let text = &source[node.byte_range()];
+
+  match text.find('\n') {
+    Some(idx) => &text[..idx],
+    None => text,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use tree_sitter::Parser;
+
+  use super::enclosing_context;
+
+  fn parse(source: &str) -> tree_sitter::Tree {
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into()).unwrap();
+    parser.parse(source, None).unwrap()
+  }
+
+  #[test]
+  fn innermost_to_outermost_order() {
+    let source = "impl Foo {\n  fn bar(&self) {\n    let x = 1;\n  }\n}";
+    let tree = parse(source);
+
+    // the `let` keyword of the `let x = 1;` statement, deep inside `fn bar`'s block
+    let leaf = tree
+      .root_node()
+      .descendant_for_byte_range(source.find("let").unwrap(), source.find("let").unwrap() + 3)
+      .unwrap();
+
+    let kinds = vec!["function_item".to_owned(), "impl_item".to_owned()];
+    let context = enclosing_context(leaf, &kinds, source);
+
+    assert_eq!(context.len(), 2);
+    assert!(context[0].text.starts_with("fn bar"));
+    assert!(context[1].text.starts_with("impl Foo"));
+  }
+
+  #[test]
+  fn filters_to_requested_kinds_only() {
+    let source = "fn bar() {\n  let x = 1;\n}";
+    let tree = parse(source);
+
+    let leaf = tree
+      .root_node()
+      .descendant_for_byte_range(source.find("let").unwrap(), source.find("let").unwrap() + 3)
+      .unwrap();
+
+    // `block` encloses `let x = 1;` too, but it isn't in the requested kind set
+    let context = enclosing_context(leaf, &["function_item".to_owned()], source);
+
+    assert_eq!(context.len(), 1);
+    assert!(context[0].text.starts_with("fn bar"));
+  }
+
+  #[test]
+  fn truncates_to_first_line() {
+    let source = "fn bar(\n  a: i32,\n) {\n  let x = 1;\n}";
+    let tree = parse(source);
+
+    let leaf = tree
+      .root_node()
+      .descendant_for_byte_range(source.find("let").unwrap(), source.find("let").unwrap() + 3)
+      .unwrap();
+
+    let context = enclosing_context(leaf, &["function_item".to_owned()], source);
+
+    assert_eq!(context.len(), 1);
+    assert_eq!(context[0].text, "fn bar(");
+  }
+}
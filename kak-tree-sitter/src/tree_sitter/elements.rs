@@ -0,0 +1,72 @@
+//! List-element text objects: individual members of list-like nodes (array/tuple/argument-list/
+//! parameter-list), found via an `entry` or `element` capture alongside the list's own capture:
+//!
+//! ```scm
+//! (arguments (_) @entry) @call.outer
+//! ```
+
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Query, QueryCursor};
+
+/// The capture names `textobjects.scm` may use to mark an individual list element.
+pub const ELEMENT_CAPTURES: &[&str] = &["entry", "element"];
+
+/// One element of a list-like node, in source order.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Element {
+  pub start_byte: usize,
+  pub end_byte: usize,
+}
+
+/// Resolve every element of `list_node`, in source order, by running `text_objects_query`
+/// against it and keeping the matches on one of [`ELEMENT_CAPTURES`].
+pub fn list_elements(text_objects_query: &Query, list_node: Node, source: &[u8]) -> Vec<Element> {
+  let element_capture_indices: Vec<u32> = text_objects_query
+    .capture_names()
+    .iter()
+    .enumerate()
+    .filter(|(_, name)| ELEMENT_CAPTURES.contains(&name.as_str()))
+    .map(|(idx, _)| idx as u32)
+    .collect();
+
+  let mut cursor = QueryCursor::new();
+  let mut matches = cursor.matches(text_objects_query, list_node, source);
+
+  let mut elements = Vec::new();
+  while let Some(m) = matches.next() {
+    for capture in m.captures {
+      if element_capture_indices.contains(&capture.index) && capture.node.parent() == Some(list_node) {
+        elements.push(Element {
+          start_byte: capture.node.start_byte(),
+          end_byte: capture.node.end_byte(),
+        });
+      }
+    }
+  }
+
+  elements.sort_by_key(|element| element.start_byte);
+  elements.dedup_by_key(|element| element.start_byte);
+
+  elements
+}
+
+/// The element containing, or starting after, `byte`; used for [`ElementOp::Select`].
+///
+/// [`ElementOp::Select`]: crate::kakoune::text_objects::ElementOp::Select
+pub fn element_at_or_after(elements: &[Element], byte: usize) -> Option<&Element> {
+  elements.iter().find(|element| element.end_byte > byte)
+}
+
+/// The first element starting strictly after `byte`; used for [`ElementOp::Next`].
+///
+/// [`ElementOp::Next`]: crate::kakoune::text_objects::ElementOp::Next
+pub fn next_element(elements: &[Element], byte: usize) -> Option<&Element> {
+  elements.iter().find(|element| element.start_byte > byte)
+}
+
+/// The last element ending at or before `byte`; used for [`ElementOp::Previous`].
+///
+/// [`ElementOp::Previous`]: crate::kakoune::text_objects::ElementOp::Previous
+pub fn previous_element(elements: &[Element], byte: usize) -> Option<&Element> {
+  elements.iter().rev().find(|element| element.end_byte <= byte)
+}
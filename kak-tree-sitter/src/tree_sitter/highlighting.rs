@@ -0,0 +1,55 @@
+//! Driving `tree-sitter-highlight` across language injections.
+
+use std::collections::HashMap;
+
+use tree_sitter_highlight::{Error, HighlightConfiguration, HighlightEvent, Highlighter};
+
+/// Resolves the [`HighlightConfiguration`] of an embedded language by name.
+///
+/// Implementations must pre-load their configs, since the injection callback needs a borrow
+/// that outlives the whole highlight call.
+pub trait InjectedLanguages {
+  /// `None` when the grammar isn't installed, so the injection is skipped rather than erroring.
+  fn highlight_configuration(&self, lang: &str) -> Option<&HighlightConfiguration>;
+}
+
+/// Pre-loaded [`HighlightConfiguration`]s, keyed by language name.
+#[derive(Debug, Default)]
+pub struct LanguageConfigs(HashMap<String, HighlightConfiguration>);
+
+impl LanguageConfigs {
+  pub fn new(configs: HashMap<String, HighlightConfiguration>) -> Self {
+    Self(configs)
+  }
+}
+
+impl InjectedLanguages for LanguageConfigs {
+  fn highlight_configuration(&self, lang: &str) -> Option<&HighlightConfiguration> {
+    self.0.get(lang)
+  }
+}
+
+/// Highlight `source` with `config`, recursing into any language injected via
+/// `@injection.language` captures or `#set! injection.language "…"` directives, resolved through
+/// `languages`. The returned events are a single merged stream, ready for
+/// [`kak_tree_sitter_highlight::KakHighlightRange::from_iter`].
+pub fn highlight_events<'a>(
+  highlighter: &'a mut Highlighter,
+  config: &'a HighlightConfiguration,
+  source: &'a [u8],
+  languages: &'a impl InjectedLanguages,
+) -> Result<impl Iterator<Item = HighlightEvent> + 'a, Error> {
+  let events = highlighter.highlight(config, source, None, move |lang| {
+    languages.highlight_configuration(lang)
+  })?;
+
+  // drop any event that comes back as a highlight error, rather than failing the buffer; a
+  // missing injected grammar never gets here, as the callback returning `None` skips it
+  Ok(events.filter_map(|event| match event {
+    Ok(event) => Some(event),
+    Err(err) => {
+      log::warn!("dropping highlight event: {err}");
+      None
+    }
+  }))
+}
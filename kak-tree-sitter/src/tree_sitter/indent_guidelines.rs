@@ -1,6 +1,6 @@
 //! Indent guidelines support in Kakoune.
 
-use std::fmt::{Display, Formatter, Write as _};
+use std::fmt::{Formatter, Write as _};
 
 const INDENT_GUIDELINE_CHAR: char = 'â”‚';
 
@@ -26,26 +26,48 @@ impl IndentGuidelines {
   /// Display as a string recognized by the `ranges` or `replace-ranges` Kakoune
   /// highlighters.
   pub fn to_kak_replace_replace_ranges_str(&self, f: &mut Formatter) {
-    self.to_kak_replace_hl_str(f, &INDENT_GUIDELINE_CHAR);
+    self.render(f, |_depth| INDENT_GUIDELINE_CHAR.to_string());
   }
 
+  /// Display as a string recognized by the `ranges` Kakoune highlighter, every guideline
+  /// using the single `ts_indent_guideline` face.
   pub fn to_kak_ranges_str(&self, f: &mut Formatter) {
-    self.to_kak_replace_hl_str(f, &"ts_indent_guideline");
+    self.to_kak_ranges_str_rainbow(f, 1);
   }
 
-  fn to_kak_replace_hl_str(&self, f: &mut Formatter, s: &impl Display) {
+  /// Display as a string recognized by the `ranges` Kakoune highlighter, cycling a palette of
+  /// `palette_size` faces (`ts_indent_guideline_1`, `ts_indent_guideline_2`, …, wrapping back
+  /// to `ts_indent_guideline_1`) by indentation depth.
+  ///
+  /// The depth of a guide is its 0-based position within its line's sorted `cols`, so the
+  /// leftmost guide on a line always gets `ts_indent_guideline_1` and deeper guides step
+  /// through the rest of the palette. `palette_size` of `1` is equivalent to
+  /// [`Self::to_kak_ranges_str`].
+  pub fn to_kak_ranges_str_rainbow(&self, f: &mut Formatter, palette_size: usize) {
+    let palette_size = palette_size.max(1);
+
+    self.render(f, |depth| {
+      if palette_size == 1 {
+        "ts_indent_guideline".to_owned()
+      } else {
+        format!("ts_indent_guideline_{}", depth % palette_size + 1)
+      }
+    });
+  }
+
+  fn render(&self, f: &mut Formatter, face_at_depth: impl Fn(usize) -> String) {
     for (line1, line2) in self.lines.iter().zip(self.lines.iter().skip(1)) {
       // display the first line + gaps if any
       for line in line1.line..line2.line {
-        for col in &line1.cols {
-          write!(f, "{line}.{col}+1|{s} ").unwrap();
+        for (depth, col) in line1.cols.iter().enumerate() {
+          write!(f, "{line}.{col}+1|{} ", face_at_depth(depth)).unwrap();
         }
       }
 
       // second line
       let line = line2.line;
-      for col in &line2.cols {
-        write!(f, "{line}.{col}+1|{s} ").unwrap();
+      for (depth, col) in line2.cols.iter().enumerate() {
+        write!(f, "{line}.{col}+1|{} ", face_at_depth(depth)).unwrap();
       }
     }
   }
@@ -59,7 +81,57 @@ pub struct IndentGuideline {
 }
 
 impl IndentGuideline {
-  pub fn new(line: usize, cols: Vec<usize>) -> Self {
+  /// `cols` is sorted on construction, as the column index within this sorted set is what
+  /// selects a guide's depth-based face in [`IndentGuidelines::to_kak_ranges_str_rainbow`].
+  pub fn new(line: usize, mut cols: Vec<usize>) -> Self {
+    cols.sort_unstable();
+
     Self { line, cols }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::fmt;
+
+  use super::{IndentGuideline, IndentGuidelines};
+
+  /// Wraps a rainbow call so tests can drive it through `format!` like any other `Formatter`
+  /// consumer.
+  struct Rainbow<'a>(&'a IndentGuidelines, usize);
+
+  impl fmt::Display for Rainbow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+      self.0.to_kak_ranges_str_rainbow(f, self.1);
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn palette_size_one_falls_back_to_bare_face() {
+    let guidelines = IndentGuidelines::new(vec![
+      IndentGuideline::new(1, vec![0, 2, 4]),
+      IndentGuideline::new(2, vec![0, 2, 4]),
+    ]);
+
+    let out = format!("{}", Rainbow(&guidelines, 1));
+    assert!(out.split_whitespace().all(|tok| tok.ends_with("|ts_indent_guideline")));
+  }
+
+  #[test]
+  fn depth_from_sorted_cols_cycles_the_palette() {
+    // cols given out of order; construction sorts them, so depth 0 is col 0, depth 1 is col 2.
+    let guidelines = IndentGuidelines::new(vec![
+      IndentGuideline::new(1, vec![4, 0, 2]),
+      IndentGuideline::new(2, vec![4, 0, 2]),
+    ]);
+
+    let out = format!("{}", Rainbow(&guidelines, 2));
+    let tokens: Vec<_> = out.split_whitespace().collect();
+
+    assert!(tokens.contains(&"1.0+1|ts_indent_guideline_1"));
+    assert!(tokens.contains(&"1.2+1|ts_indent_guideline_2"));
+    // depth 2 wraps back around to face 1
+    assert!(tokens.contains(&"1.4+1|ts_indent_guideline_1"));
+  }
+}
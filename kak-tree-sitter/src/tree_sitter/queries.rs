@@ -2,6 +2,9 @@
 
 use std::{fs, path::Path};
 
+use tree_sitter::Language;
+use tree_sitter_highlight::HighlightConfiguration;
+
 #[derive(Debug)]
 pub struct Queries {
   pub highlights: Option<String>,
@@ -26,4 +29,20 @@ impl Queries {
       text_objects,
     }
   }
+
+  /// Build a [`HighlightConfiguration`] for `language` (named `lang`) out of the queries found
+  /// in this directory, including `injections` and `locals`. `None` when there is no
+  /// `highlights.scm`, or the queries fail to compile against `language`.
+  pub fn highlight_configuration(&self, language: Language, lang: &str) -> Option<HighlightConfiguration> {
+    let highlights = self.highlights.as_deref()?;
+
+    HighlightConfiguration::new(
+      language,
+      lang,
+      highlights,
+      self.injections.as_deref().unwrap_or(""),
+      self.locals.as_deref().unwrap_or(""),
+    )
+    .ok()
+  }
 }
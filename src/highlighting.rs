@@ -31,15 +31,17 @@ impl KakHighlightRange {
     }
   }
 
-  /// Given an iterator of [`HighlightEvent`], generate a list of Kakoune highlights.
+  /// Given an iterator of [`HighlightEvent`], generate a list of Kakoune highlights. `tab_width`
+  /// is how many columns a `\t` expands to.
   pub fn from_iter(
     source: &str,
     hl_names: &[&str],
     hl_events: impl Iterator<Item = HighlightEvent>,
+    tab_width: usize,
   ) -> Vec<Self> {
     let mut kak_hls = Vec::new();
     let mut faces: Vec<&str> = Vec::new();
-    let mut mapper = ByteLineColMapper::new(source.char_indices());
+    let mut mapper = ByteLineColMapper::new(source.char_indices(), tab_width);
 
     // iterate on the highlight event
     for event in hl_events {
@@ -49,8 +51,6 @@ impl KakHighlightRange {
             continue;
           }
 
-          println!("{start}-{end}");
-
           mapper.advance(start);
           let line_start = mapper.line();
           let col_start = mapper.col();
@@ -80,7 +80,6 @@ impl KakHighlightRange {
       }
     }
 
-    println!("{kak_hls:#?}");
     kak_hls
   }
 
@@ -93,29 +92,26 @@ impl KakHighlightRange {
   }
 }
 
-/// Map byte indices to line and column.
+/// Map byte indices to line and column, in the byte-column coordinates Kakoune's `ranges`
+/// highlighter expects, expanding `\t` to `tab_width` columns and skipping zero-width marks.
 #[derive(Debug)]
-struct ByteLineColMapper<C> {
-  chars: C,
-  byte_idx: usize,
+struct ByteLineColMapper<C: Iterator<Item = (usize, char)>> {
+  chars: std::iter::Peekable<C>,
   line: usize,
   col: usize,
-  change_line: bool,
+  tab_width: usize,
 }
 
 impl<C> ByteLineColMapper<C>
 where
   C: Iterator<Item = (usize, char)>,
 {
-  fn new(mut chars: C) -> Self {
-    chars.next();
-
+  fn new(chars: C, tab_width: usize) -> Self {
     Self {
-      chars,
-      byte_idx: 0,
+      chars: chars.peekable(),
       line: 1,
       col: 1,
-      change_line: false,
+      tab_width,
     }
   }
 
@@ -127,32 +123,42 @@ where
     self.col
   }
 
+  /// Advance up to (but not including) byte offset `til`, leaving [`Self::line`] / [`Self::col`]
+  /// pointing at the character starting there.
   fn advance(&mut self, til: usize) {
-    loop {
-      if self.byte_idx >= til {
+    while let Some(&(idx, c)) = self.chars.peek() {
+      if idx >= til {
         break;
       }
 
-      if let Some((idx, c)) = self.chars.next() {
-        println!("read {c}");
-        self.byte_idx = idx;
-
-        if self.change_line {
-          self.line += 1;
-          self.col = 0;
-        }
-
-        self.change_line = c == '\n';
+      self.chars.next();
 
-        // TODO: we probably want to compute the « display width » of `c` here instead
-        self.col += 1;
+      if c == '\n' {
+        self.line += 1;
+        self.col = 1;
+      } else if c == '\t' {
+        self.col += self.tab_width;
+      } else if is_zero_width(c) {
+        // combining marks, ZWJ/ZWNJ and friends ride on the previous column rather than
+        // claiming one of their own; NBSP and other problematic-but-visible whitespace fall
+        // through to the byte-length branch below like any other character
       } else {
-        break;
+        self.col += c.len_utf8();
       }
     }
   }
 }
 
+/// Whether `c` is a zero-width combining mark or joiner that shouldn't advance the column.
+fn is_zero_width(c: char) -> bool {
+  matches!(c,
+    '\u{0300}'..='\u{036F}' // combining diacritical marks
+    | '\u{200B}'..='\u{200D}' // zero-width space/ZWNJ/ZWJ
+    | '\u{FE00}'..='\u{FE0F}' // variation selectors
+    | '\u{FEFF}' // zero-width no-break space / BOM
+  )
+}
+
 #[cfg(test)]
 mod tests {
   use super::ByteLineColMapper;
@@ -160,7 +166,7 @@ mod tests {
   #[test]
   fn byte_line_col_mapper() {
     let source = "const x: &'str = \"Hello, world!\";\nconst y = 3;";
-    let mut mapper = ByteLineColMapper::new(source.char_indices());
+    let mut mapper = ByteLineColMapper::new(source.char_indices(), 8);
 
     assert_eq!(mapper.line(), 1);
     assert_eq!(mapper.col(), 1);
@@ -177,4 +183,84 @@ mod tests {
     assert_eq!(mapper.line(), 2);
     assert_eq!(mapper.col(), 1);
   }
+
+  #[test]
+  fn byte_line_col_mapper_utf8_and_tab() {
+    // "café\tdog\nnaïve": `é` and `ï` are both two-byte UTF-8 characters.
+    let source = "café\tdog\nnaïve";
+    let mut mapper = ByteLineColMapper::new(source.char_indices(), 1);
+
+    // byte 3: right after the three one-byte `c`/`a`/`f`, before the two-byte `é`.
+    mapper.advance(3);
+    assert_eq!(mapper.line(), 1);
+    assert_eq!(mapper.col(), 4);
+
+    // byte 5: right after `é` (which took up 2 columns, not 1), before the tab.
+    mapper.advance(5);
+    assert_eq!(mapper.line(), 1);
+    assert_eq!(mapper.col(), 6);
+
+    // byte 6: right after the tab, which at tab_width 1 advances the column like any other
+    // character.
+    mapper.advance(6);
+    assert_eq!(mapper.line(), 1);
+    assert_eq!(mapper.col(), 7);
+
+    // byte 10: right after the `\n`, which resets to column 1 of the next line.
+    mapper.advance(10);
+    assert_eq!(mapper.line(), 2);
+    assert_eq!(mapper.col(), 1);
+
+    // byte 14: right after the second two-byte character, `ï`.
+    mapper.advance(14);
+    assert_eq!(mapper.line(), 2);
+    assert_eq!(mapper.col(), 5);
+  }
+
+  #[test]
+  fn byte_line_col_mapper_configurable_tab_width() {
+    // "a\tb": a tab_width of 4 advances 4 columns instead of 1.
+    let source = "a\tb";
+    let mut mapper = ByteLineColMapper::new(source.char_indices(), 4);
+
+    mapper.advance(1);
+    assert_eq!(mapper.col(), 2);
+
+    mapper.advance(2);
+    assert_eq!(mapper.col(), 6);
+  }
+
+  #[test]
+  fn byte_line_col_mapper_skips_zero_width_marks() {
+    // "e\u{0301}x": `e` followed by a combining acute accent, then `x`. The combining mark
+    // doesn't claim its own column.
+    let source = "e\u{0301}x";
+    let mut mapper = ByteLineColMapper::new(source.char_indices(), 8);
+
+    // byte 1: right after `e`.
+    mapper.advance(1);
+    assert_eq!(mapper.col(), 2);
+
+    // byte 3: right after the two-byte combining mark, which didn't advance the column.
+    mapper.advance(3);
+    assert_eq!(mapper.col(), 2);
+
+    // byte 4: right after `x`.
+    mapper.advance(4);
+    assert_eq!(mapper.col(), 3);
+  }
+
+  #[test]
+  fn byte_line_col_mapper_nbsp_advances_like_any_char() {
+    // "a\u{00A0}b": NBSP is visible, problematic whitespace, not a zero-width mark, so it still
+    // advances the column by its own (two-byte) UTF-8 length.
+    let source = "a\u{00A0}b";
+    let mut mapper = ByteLineColMapper::new(source.char_indices(), 8);
+
+    mapper.advance(1);
+    assert_eq!(mapper.col(), 2);
+
+    mapper.advance(3);
+    assert_eq!(mapper.col(), 4);
+  }
 }